@@ -0,0 +1,85 @@
+// A fixed-size bit set backed by `Vec<u64>`, used in place of `Vec<bool>`
+// scratch arrays for visited/has-predecessor flags. One bit per element
+// instead of one byte gives an 8x memory reduction and keeps the working set
+// in cache for the large unitig sets this crate is meant to scale to.
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        BitSet { words: vec![0_u64; len.div_ceil(64)], len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set(&mut self, i: usize) {
+        self.words[i >> 6] |= 1_u64 << (i & 63);
+    }
+
+    pub fn unset(&mut self, i: usize) {
+        self.words[i >> 6] &= !(1_u64 << (i & 63));
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        (self.words[i >> 6] >> (i & 63)) & 1 == 1
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = 0);
+    }
+
+    /// Iterates over the indices of set bits in increasing order, walking
+    /// only the nonzero words and using `trailing_zeros` to find each set bit
+    /// within a word instead of testing every index one at a time.
+    pub fn iter_set_bits(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, &word)| {
+            let mut remaining = word;
+            std::iter::from_fn(move || {
+                if remaining == 0 {
+                    None
+                } else {
+                    let bit = remaining.trailing_zeros() as usize;
+                    remaining &= remaining - 1; // Clear the lowest set bit.
+                    Some(word_idx * 64 + bit)
+                }
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_contains_and_iterate() {
+        let mut bs = BitSet::new(130);
+        for i in [0, 1, 63, 64, 65, 129] {
+            bs.set(i);
+        }
+        for i in [0, 1, 63, 64, 65, 129] {
+            assert!(bs.contains(i));
+        }
+        assert!(!bs.contains(2));
+
+        let collected: Vec<usize> = bs.iter_set_bits().collect();
+        assert_eq!(collected, vec![0, 1, 63, 64, 65, 129]);
+    }
+
+    #[test]
+    fn clear_resets_all_bits() {
+        let mut bs = BitSet::new(70);
+        bs.set(5);
+        bs.set(69);
+        bs.clear();
+        assert_eq!(bs.iter_set_bits().count(), 0);
+    }
+}
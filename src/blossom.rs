@@ -0,0 +1,181 @@
+// Edmonds' blossom algorithm: maximum matching in a general (non-bipartite)
+// graph. Used by `pick_orientations_matching` to match up unitig ends, where
+// the two "sides" aren't a bipartition (either end of a unitig can be glued
+// to either end of another), so Hopcroft-Karp doesn't apply.
+//
+// This is the textbook BFS formulation: grow alternating trees from every
+// unmatched vertex, and whenever two branches of the same tree meet, the
+// cycle they form (a blossom, always of odd length) is contracted into a
+// single super-vertex so the search can keep treating the tree as bipartite.
+// `base[v]` tracks which contracted blossom `v` currently belongs to and
+// plays the role of the union-find structure mentioned in the writeup: it is
+// reset at the start of every search and lazily "unioned" by overwriting it
+// for every vertex absorbed into a new blossom.
+
+pub const NIL: usize = usize::MAX;
+
+pub struct Graph {
+    pub n: usize,
+    adj: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    pub fn new(n: usize) -> Self {
+        Graph { n, adj: vec![Vec::new(); n] }
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.adj[u].push(v);
+        self.adj[v].push(u);
+    }
+}
+
+/// Returns `matching[v] = u` iff `v` is matched to `u`, or `NIL` if `v` is unmatched.
+pub fn maximum_matching(g: &Graph) -> Vec<usize> {
+    let n = g.n;
+    let mut matching = vec![NIL; n];
+
+    for root in 0..n {
+        if matching[root] == NIL {
+            if let Some((parent, exposed)) = find_augmenting_path(g, root, &matching) {
+                // Walk back from the exposed vertex to the root, flipping
+                // each matched/unmatched edge pair along the way.
+                let mut v = exposed;
+                while v != NIL {
+                    let pv = parent[v];
+                    let next = matching[pv];
+                    matching[v] = pv;
+                    matching[pv] = v;
+                    v = next;
+                }
+            }
+        }
+    }
+
+    matching
+}
+
+// State for a single BFS search for an augmenting path from `root`.
+struct Search {
+    parent: Vec<usize>, // Alternating-tree parent of each vertex, NIL if unset.
+    base: Vec<usize>,   // Which blossom each vertex currently belongs to.
+    in_tree: Vec<bool>, // Whether a vertex has been enqueued this search.
+}
+
+fn lca(search: &Search, matching: &[usize], mut a: usize, mut b: usize) -> usize {
+    let n = matching.len();
+    let mut on_path_to_root = vec![false; n];
+
+    loop {
+        a = search.base[a];
+        on_path_to_root[a] = true;
+        if matching[a] == NIL {
+            break;
+        }
+        a = search.parent[matching[a]];
+    }
+
+    loop {
+        b = search.base[b];
+        if on_path_to_root[b] {
+            return b;
+        }
+        b = search.parent[matching[b]];
+    }
+}
+
+// Marks every vertex on the path from `v` up to blossom base `base_vertex` as
+// part of the new blossom, and rewires their tree parent through `child` so
+// the alternating structure is preserved after contraction.
+fn mark_blossom_path(search: &mut Search, in_blossom: &mut [bool], matching: &[usize], mut v: usize, base_vertex: usize, mut child: usize) {
+    while search.base[v] != base_vertex {
+        in_blossom[search.base[v]] = true;
+        in_blossom[search.base[matching[v]]] = true;
+        search.parent[v] = child;
+        child = matching[v];
+        v = search.parent[matching[v]];
+    }
+}
+
+// Runs one BFS from `root`, contracting blossoms as they're discovered, and
+// returns the alternating-tree parent pointers together with the unmatched
+// vertex an augmenting path reaches, if any.
+fn find_augmenting_path(g: &Graph, root: usize, matching: &[usize]) -> Option<(Vec<usize>, usize)> {
+    let n = g.n;
+    let mut search = Search {
+        parent: vec![NIL; n],
+        base: (0..n).collect(),
+        in_tree: vec![false; n],
+    };
+
+    search.in_tree[root] = true;
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(root);
+
+    while let Some(v) = queue.pop_front() {
+        for &to in g.adj[v].iter() {
+            if search.base[v] == search.base[to] || matching[v] == to {
+                continue; // Same blossom, or the matched edge we arrived on.
+            }
+
+            if to == root || (matching[to] != NIL && search.parent[matching[to]] != NIL) {
+                // Found a blossom: the cycle root -> ... -> v -> to -> ... -> root.
+                let base_vertex = lca(&search, matching, v, to);
+                let mut in_blossom = vec![false; n];
+                mark_blossom_path(&mut search, &mut in_blossom, matching, v, base_vertex, to);
+                mark_blossom_path(&mut search, &mut in_blossom, matching, to, base_vertex, v);
+
+                for i in 0..n {
+                    if in_blossom[search.base[i]] {
+                        search.base[i] = base_vertex;
+                        if !search.in_tree[i] {
+                            search.in_tree[i] = true;
+                            queue.push_back(i);
+                        }
+                    }
+                }
+            } else if search.parent[to] == NIL {
+                search.parent[to] = v;
+                if matching[to] == NIL {
+                    return Some((search.parent, to));
+                }
+                search.in_tree[matching[to]] = true;
+                queue.push_back(matching[to]);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangle_plus_pendant() {
+        // A triangle (odd cycle, needs blossom contraction) with a pendant
+        // vertex hanging off one corner. Maximum matching has size 2.
+        let mut g = Graph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 0);
+        g.add_edge(2, 3);
+
+        let matching = maximum_matching(&g);
+        let matched = matching.iter().filter(|&&m| m != NIL).count();
+        assert_eq!(matched, 4); // 2 matched edges (e.g. 0-1, 2-3) cover all 4 vertices.
+    }
+
+    #[test]
+    fn simple_path() {
+        let mut g = Graph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 3);
+
+        let matching = maximum_matching(&g);
+        let matched = matching.iter().filter(|&&m| m != NIL).count();
+        assert_eq!(matched, 4); // Perfect matching: 0-1, 2-3.
+    }
+}
@@ -0,0 +1,98 @@
+const ARITY: usize = 4;
+
+/// A 4-ary max-heap of `(priority, value)` pairs. A higher branching factor
+/// than the usual binary heap means shallower trees at the cost of more
+/// comparisons per level, which suits workloads that interleave many pushes
+/// with pops, such as `pick_orientations_greedy_longest`'s frontier.
+pub struct DaryHeap {
+    data: Vec<(usize, usize)>,
+}
+
+impl DaryHeap {
+    pub fn new() -> Self {
+        DaryHeap { data: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn push(&mut self, priority: usize, value: usize) {
+        self.data.push((priority, value));
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub fn pop_max(&mut self) -> Option<(usize, usize)> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let last = self.data.len() - 1;
+        self.data.swap(0, last);
+        let top = self.data.pop();
+        self.sift_down(0);
+        top
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / ARITY;
+            if self.data[i] > self.data[parent] {
+                self.data.swap(i, parent);
+                i = parent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let first_child = i * ARITY + 1;
+            if first_child >= self.data.len() {
+                break;
+            }
+            let last_child = (first_child + ARITY).min(self.data.len());
+
+            let mut largest = i;
+            for c in first_child..last_child {
+                if self.data[c] > self.data[largest] {
+                    largest = c;
+                }
+            }
+            if largest == i {
+                break;
+            }
+            self.data.swap(i, largest);
+            i = largest;
+        }
+    }
+}
+
+impl Default for DaryHeap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_decreasing_priority_order() {
+        let mut heap = DaryHeap::new();
+        for (priority, value) in [(3, 30), (1, 10), (4, 40), (1, 11), (5, 50), (9, 90), (2, 20)] {
+            heap.push(priority, value);
+        }
+
+        let mut popped = Vec::new();
+        while let Some((priority, _)) = heap.pop_max() {
+            popped.push(priority);
+        }
+
+        let mut expected = popped.clone();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(popped, expected);
+        assert!(heap.is_empty());
+    }
+}
@@ -14,8 +14,10 @@ pub struct DBG {
     pub unitig_db: jseqio::seq_db::SeqDB,
 }
 
+// pub(crate) so `dbg_rethink`'s rayon-parallel construction can share these
+// instead of redeclaring its own copy.
 #[derive(Copy, Clone, Debug, PartialEq)]
-enum Position{ // Used internally in construction
+pub(crate) enum Position{ // Used internally in construction
     Start,
     End,
 }
@@ -27,14 +29,14 @@ pub enum Orientation{
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
-struct MapValue{ // Used internally in construction
-    unitig_id: usize,
-    position: Position,
+pub(crate) struct MapValue{ // Used internally in construction
+    pub(crate) unitig_id: usize,
+    pub(crate) position: Position,
 }
 
 impl DBG {
 
-    fn new(n_unitigs : usize) -> Self {
+    pub(crate) fn new(n_unitigs : usize) -> Self {
         DBG{out_edges: vec![Vec::new(); n_unitigs * 2], in_edges: vec![Vec::new(); n_unitigs * 2], n_unitigs, unitig_db: jseqio::seq_db::SeqDB::new()}
     }
 
@@ -141,4 +143,212 @@ impl DBG {
         dbg
 
     }
+
+    /// Like [`DBG::build`], but avoids hashing the `(k-1)`-mer borders as
+    /// borrowed byte slices. Instead each border is packed into a `u128`
+    /// (2 bits/base), and the `(packed_key, unitig_id, Position)` triples are
+    /// sorted with an LSD radix sort so that edge endpoints can be grouped by
+    /// scanning runs of equal keys via binary search, rather than paying for a
+    /// `Vec` allocation per distinct hash map key. This keeps construction from
+    /// needing borrowed slices into `unitigs`/`unitigs_rc` at all, at the cost
+    /// of only supporting `k - 1 <= 64` and borders made up of uppercase
+    /// `A`/`C`/`G`/`T`; returns both inputs back in `Err` otherwise so the
+    /// caller can fall back to [`DBG::build`] without having to reread them.
+    pub fn build_packed(unitigs: jseqio::seq_db::SeqDB, unitigs_rc: jseqio::seq_db::SeqDB, k: usize) -> Result<DBG, (jseqio::seq_db::SeqDB, jseqio::seq_db::SeqDB)> {
+
+        use Orientation::*;
+
+        // Checked up front, against both `unitigs` and `unitigs_rc` (whose own
+        // borders are exactly the `first_rc`/`last_rc` values used below), so
+        // that on failure neither input has been consumed yet and both can be
+        // handed straight back to the caller.
+        if k == 0 || k - 1 > 64 || !all_borders_packable(&unitigs, k) || !all_borders_packable(&unitigs_rc, k) {
+            return Err((unitigs, unitigs_rc));
+        }
+
+        let n = unitigs.sequence_count();
+
+        log::info!("Packing border k-mers");
+
+        let mut entries: Vec<(u128, usize, Position)> = Vec::with_capacity(n * 2);
+        for i in 0..n {
+            let unitig = unitigs.get(i);
+            let first = &unitig.seq[..k-1];
+            let last = &unitig.seq[unitig.seq.len()-(k-1)..];
+
+            entries.push((pack_kmer(first).expect("validated packable above"), i, Position::Start));
+            entries.push((pack_kmer(last).expect("validated packable above"), i, Position::End));
+        }
+
+        radix_sort_entries(&mut entries);
+
+        // Binary search into the sorted array to find the run of entries
+        // whose packed key equals `key`, i.e. the occurrences of that border.
+        // A nested `fn` rather than a closure: closures don't get the
+        // input/output lifetime elision a plain `fn` does, so the compiler
+        // can't otherwise prove the returned slice borrows from `entries`.
+        fn lookup(entries: &[(u128, usize, Position)], key: u128) -> &[(u128, usize, Position)] {
+            let start = entries.partition_point(|e| e.0 < key);
+            let end = start + entries[start..].partition_point(|e| e.0 == key);
+            &entries[start..end]
+        }
+
+        log::info!("Building edges");
+        let mut dbg = DBG::new(n);
+        for i in 0..n {
+            let unitig = unitigs.get(i);
+            let unitig_rc = unitigs_rc.get(i);
+
+            let first = &unitig.seq[..k-1];
+            let last = &unitig.seq[unitig.seq.len()-(k-1)..];
+
+            let first_rc = &unitig_rc.seq[unitig_rc.seq.len()-(k-1)..];
+            let last_rc = &unitig_rc.seq[..k-1];
+
+            for &(_, id, pos) in lookup(&entries, pack_kmer(last).expect("validated packable above")) {
+                if pos == Position::Start {
+                    dbg.add_edge(i, id, Forward, Forward)
+                }
+            }
+
+            for &(_, id, pos) in lookup(&entries, pack_kmer(last_rc).expect("validated packable above")) {
+                if pos == Position::End {
+                    dbg.add_edge(i, id, Forward, Reverse)
+                }
+            }
+
+            for &(_, id, pos) in lookup(&entries, pack_kmer(first_rc).expect("validated packable above")) {
+                if pos == Position::Start {
+                    dbg.add_edge(i, id, Reverse, Forward)
+                }
+            }
+
+            for &(_, id, pos) in lookup(&entries, pack_kmer(first).expect("validated packable above")) {
+                if pos == Position::End {
+                    dbg.add_edge(i, id, Reverse, Reverse)
+                }
+            }
+        }
+
+        dbg.unitig_db = unitigs;
+        Ok(dbg)
+    }
+}
+
+// Checks that every first/last `(k-1)`-mer border of `unitigs` can be packed
+// by `pack_kmer`, i.e. is short enough and made up only of uppercase ACGT.
+fn all_borders_packable(unitigs: &jseqio::seq_db::SeqDB, k: usize) -> bool {
+    (0..unitigs.sequence_count()).all(|i| {
+        let unitig = unitigs.get(i);
+        let first = &unitig.seq[..k - 1];
+        let last = &unitig.seq[unitig.seq.len() - (k - 1)..];
+        pack_kmer(first).is_some() && pack_kmer(last).is_some()
+    })
+}
+
+// Packs a DNA string into 2 bits/base, most significant base first.
+// Returns None if the string is too long to fit in a u128 or contains a
+// non-ACGT base; `build_packed` checks every border up front and hands its
+// inputs back if any of them fails to pack.
+fn pack_kmer(bases: &[u8]) -> Option<u128> {
+    if bases.len() > 64 {
+        return None;
+    }
+    let mut key: u128 = 0;
+    for &b in bases {
+        let code: u128 = match b {
+            b'A' => 0,
+            b'C' => 1,
+            b'G' => 2,
+            b'T' => 3,
+            _ => return None,
+        };
+        key = (key << 2) | code;
+    }
+    Some(key)
+}
+
+// LSD radix sort of (packed_key, unitig_id, Position) triples by packed_key,
+// one byte at a time using a counting sort pass, so the whole u128 key is
+// sorted in 16 linear passes instead of paying for comparisons in a
+// general-purpose sort.
+fn radix_sort_entries(entries: &mut Vec<(u128, usize, Position)>) {
+    let mut buf = entries.clone();
+
+    for byte in 0..16 {
+        let shift = byte * 8;
+        let mut counts = [0usize; 257];
+
+        for e in entries.iter() {
+            let b = ((e.0 >> shift) & 0xFF) as usize;
+            counts[b + 1] += 1;
+        }
+        for i in 0..256 {
+            counts[i + 1] += counts[i];
+        }
+        for &e in entries.iter() {
+            let b = ((e.0 >> shift) & 0xFF) as usize;
+            buf[counts[b]] = e;
+            counts[b] += 1;
+        }
+
+        std::mem::swap(entries, &mut buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_pair(seqs: &[&[u8]]) -> (jseqio::seq_db::SeqDB, jseqio::seq_db::SeqDB) {
+        let mut db = jseqio::seq_db::SeqDB::new();
+        for seq in seqs {
+            db.push_seq(seq);
+        }
+
+        let mut rc_db = jseqio::seq_db::SeqDB::new();
+        for seq in seqs {
+            rc_db.push_seq(&jseqio::reverse_complement(seq));
+        }
+
+        (db, rc_db)
+    }
+
+    #[test]
+    fn build_packed_matches_build_on_acgt_input() {
+        let k = 4;
+        let seqs: Vec<&[u8]> = vec![b"ACGTACG", b"TACGTTT"];
+
+        let (db, rc_db) = make_pair(&seqs);
+        let packed = match DBG::build_packed(db, rc_db, k) {
+            Ok(dbg) => dbg,
+            Err(_) => panic!("all-ACGT input must pack"),
+        };
+
+        let (db, rc_db) = make_pair(&seqs);
+        let hashed = DBG::build(db, rc_db, k);
+
+        assert_eq!(packed.n_unitigs, hashed.n_unitigs);
+        let packed_edges: usize = packed.out_edges.iter().map(|e| e.len()).sum();
+        let hashed_edges: usize = hashed.out_edges.iter().map(|e| e.len()).sum();
+        assert_eq!(packed_edges, hashed_edges);
+    }
+
+    #[test]
+    fn build_packed_falls_back_on_non_acgt_border() {
+        let k = 4;
+        // A soft-masked base in a border: `build` hashes it fine, but
+        // `build_packed` can't pack it and must hand both inputs back so the
+        // caller can retry with `build`.
+        let seqs: Vec<&[u8]> = vec![b"acgTACG", b"TACGTTT"];
+
+        let (db, rc_db) = make_pair(&seqs);
+        let (db, rc_db) = match DBG::build_packed(db, rc_db, k) {
+            Err(inputs) => inputs,
+            Ok(_) => panic!("lowercase border must not pack"),
+        };
+
+        let dbg = DBG::build(db, rc_db, k);
+        assert_eq!(dbg.n_unitigs, 2);
+    }
 }
\ No newline at end of file
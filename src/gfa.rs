@@ -0,0 +1,130 @@
+use std::io::{self, Write};
+
+use crate::dbg::{Orientation, DBG};
+
+/// Writes the oriented unitig de Bruijn graph as a GFA1 file.
+///
+/// One `S` segment is emitted per unitig, in the orientation chosen by
+/// `optimize_unitig_orientation` (or any other `pick_orientations_*` function).
+/// A unitig can have more than one orientation-consistent predecessor (e.g. a
+/// pair of unitigs sharing borders on both ends forms a 2-cycle in the doubled
+/// graph), but `evaluate` only tracks whether a unitig has *some*
+/// predecessor, not how many; to keep the link set consistent with that
+/// count instead of silently emitting an extra link per redundant arc, at
+/// most one `L` link is kept per target unitig (the first orientation-
+/// consistent arc into it, in node order). Because the segment sequences are
+/// already written out reverse-complemented when their orientation is
+/// `Reverse`, every surviving link connects the plus strand of one segment to
+/// the plus strand of the next: the `(k-1)`-mer overlap implied by the DBG
+/// border match becomes the CIGAR `{k-1}M`.
+pub fn write_gfa<W: Write>(
+    writer: &mut W,
+    dbg: &DBG,
+    orientations: &[Orientation],
+    k: usize,
+) -> io::Result<()> {
+    writeln!(writer, "H\tVN:Z:1.0")?;
+
+    let n = dbg.n_unitigs;
+
+    for i in 0..n {
+        let rec = dbg.unitig_db.get(i);
+        let mut seq = rec.seq.to_vec();
+        if orientations[i] == Orientation::Reverse {
+            jseqio::reverse_complement_in_place(&mut seq);
+        }
+        writeln!(writer, "S\t{}\t{}", i, String::from_utf8_lossy(&seq))?;
+    }
+
+    // Tracks which unitigs already have a link into them, so a redundant
+    // second orientation-consistent arc into the same target (see the doc
+    // comment above) doesn't produce a second `L` line.
+    let mut has_link: Vec<bool> = vec![false; n];
+
+    for v in 0..(2 * n) {
+        let v_is_flipped = orientations[v % n] == Orientation::Reverse;
+        if v_is_flipped != (v >= n) {
+            continue; // Node v is not the one in the chosen orientation.
+        }
+        for &u in dbg.out_edges[v].iter() {
+            let u_is_flipped = orientations[u % n] == Orientation::Reverse;
+            if u_is_flipped != (u >= n) {
+                continue; // Node u is not the one in the chosen orientation.
+            }
+            if has_link[u % n] {
+                continue; // Already linked into this target; see doc comment.
+            }
+            has_link[u % n] = true;
+            writeln!(writer, "L\t{}\t+\t{}\t+\t{}M", v % n, u % n, k - 1)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pick_orientations_bipartite_matching;
+
+    fn get_dbg(seqs: Vec<&[u8]>, k: usize) -> DBG {
+        let rc_seqs = seqs.iter().map(|s| jseqio::reverse_complement(s));
+
+        let mut db = jseqio::seq_db::SeqDB::new();
+        for seq in seqs.iter() {
+            db.push_seq(seq);
+        }
+
+        let mut rc_db = jseqio::seq_db::SeqDB::new();
+        for rc_seq in rc_seqs {
+            rc_db.push_seq(&rc_seq);
+        }
+
+        DBG::build(db, rc_db, k)
+    }
+
+    #[test]
+    fn write_gfa_emits_one_segment_per_unitig_and_consistent_links(){
+        // Same fixture used in lib.rs's test_bipartite_matching_picks_orientation_consistent_arc:
+        // three unitigs including a pair (1, 2) joined by two parallel border
+        // overlaps, which `pick_orientations_bipartite_matching` can leave both
+        // orientation-consistent at once (a 2-cycle between those two
+        // segments); `write_gfa` is expected to dedup that down to one link
+        // per target, same as `evaluate`.
+        let k = 3;
+        let data: Vec<&[u8]> = vec![b"AATG", b"GTCA", b"CATGT"];
+
+        let dbg = get_dbg(data, k);
+        let orientations = pick_orientations_bipartite_matching(&dbg);
+        let n_has_pred = crate::evaluate(&orientations, &dbg);
+
+        let mut out = Vec::<u8>::new();
+        write_gfa(&mut out, &dbg, &orientations, k).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "H\tVN:Z:1.0");
+
+        let s_lines: Vec<&str> = lines.iter().filter(|l| l.starts_with("S\t")).copied().collect();
+        let l_lines: Vec<&str> = lines.iter().filter(|l| l.starts_with("L\t")).copied().collect();
+
+        assert_eq!(s_lines.len(), dbg.n_unitigs);
+        // write_gfa keeps at most one link per target unitig, so link count
+        // matches the number of unitigs `evaluate` says have a predecessor,
+        // even when a pair of unitigs has more than one orientation-
+        // consistent arc between them.
+        assert_eq!(l_lines.len(), n_has_pred);
+
+        // Every link must name two distinct unitigs and carry the (k-1)M
+        // overlap CIGAR -- never a self-loop, and never an overlap length
+        // other than k-1.
+        for line in &l_lines {
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields[0], "L");
+            assert_eq!(fields[2], "+");
+            assert_eq!(fields[4], "+");
+            assert_eq!(fields[5], format!("{}M", k - 1));
+            assert_ne!(fields[1], fields[3]);
+        }
+    }
+}
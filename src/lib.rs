@@ -1,6 +1,15 @@
+pub mod bitset;
+pub mod blossom;
+pub mod dary_heap;
 pub mod dbg;
+pub mod dbg_rethink;
+pub mod gfa;
+pub mod matching;
+pub mod simplitigs;
 
+use bitset::BitSet;
 use dbg::*;
+use matching::BipartiteGraph;
 
 /// A stream of ASCII-encoded DNA-sequences. This is not necessarily a standard Rust iterator
 /// because we want to support streaming sequences from disk, which is not possible
@@ -51,10 +60,12 @@ impl Direction {
     }
 }
 
-/// Given a stream of unitigs, returns a vector of orientations, one for each sequence, aiming to
-/// minimize the number of unitigs which do not have an incoming edge in the de Bruijn graph
-/// of order k.
-pub fn optimize_unitig_orientation<'a, SS: SeqStream<'a>>(mut input: SS, k: usize) -> Vec<Orientation>{
+/// Drains a stream of unitigs into a forward and a reverse-complemented
+/// `SeqDB`, the shared input format every `DBG::build*` constructor takes.
+/// Exposed so that callers which need to pick a specific constructor (e.g.
+/// [`DBG::build_parallel`](dbg_rethink) for a rayon-parallel build) don't
+/// have to duplicate this setup.
+pub fn read_unitigs<'a, SS: SeqStream<'a>>(mut input: SS) -> (jseqio::seq_db::SeqDB, jseqio::seq_db::SeqDB) {
     let mut db = jseqio::seq_db::SeqDB::new();
     let mut rc_db = jseqio::seq_db::SeqDB::new();
     let mut rc_buf = Vec::<u8>::new();
@@ -66,9 +77,29 @@ pub fn optimize_unitig_orientation<'a, SS: SeqStream<'a>>(mut input: SS, k: usiz
         jseqio::reverse_complement_in_place(&mut rc_buf);
         rc_db.push_seq(&rc_buf);
     }
+    (db, rc_db)
+}
 
-    let dbg = DBG::build(db, rc_db, k);
+/// Given a stream of unitigs, builds the 2n-node de Bruijn graph used internally
+/// by the `pick_orientations_*` functions. Exposed so that callers which need
+/// the graph itself (e.g. to serialize it) don't have to duplicate this setup.
+pub fn build_dbg<'a, SS: SeqStream<'a>>(input: SS, k: usize) -> DBG {
+    let (db, rc_db) = read_unitigs(input);
+
+    // The packed builder only supports (k-1)-mers up to 64 bases made up of
+    // uppercase ACGT; fall back to the hashing builder for anything else
+    // (larger k, or input containing N/ambiguity codes/soft-masked bases).
+    match DBG::build_packed(db, rc_db, k) {
+        Ok(dbg) => dbg,
+        Err((db, rc_db)) => DBG::build(db, rc_db, k),
+    }
+}
 
+/// Given a stream of unitigs, returns a vector of orientations, one for each sequence, aiming to
+/// minimize the number of unitigs which do not have an incoming edge in the de Bruijn graph
+/// of order k.
+pub fn optimize_unitig_orientation<'a, SS: SeqStream<'a>>(input: SS, k: usize) -> Vec<Orientation>{
+    let dbg = build_dbg(input, k);
     pick_orientations_with_non_switching_bfs(&dbg)
 }
 
@@ -77,7 +108,7 @@ pub fn optimize_unitig_orientation<'a, SS: SeqStream<'a>>(mut input: SS, k: usiz
 // path is a sequence of unitigs that can be merged together left-to-right starting from 
 // the root, using the orientations saved during the search. The backward search is the 
 // same but in the other direction.
-fn bfs(mut root: usize, dir: Direction, oris: &mut [Orientation], visited: &mut [bool], dbg: &DBG ) {
+fn bfs(mut root: usize, dir: Direction, oris: &mut [Orientation], visited: &mut BitSet, dbg: &DBG ) {
 
     if dir == Direction::Backward {
         // If we are going backward, it's the same as walking forward but
@@ -90,8 +121,8 @@ fn bfs(mut root: usize, dir: Direction, oris: &mut [Orientation], visited: &mut
 
     while let Some(v) = queue.pop_front() {
 
-        if visited[v % dbg.n_unitigs] { continue }
-        visited[v % dbg.n_unitigs] = true;
+        if visited.contains(v % dbg.n_unitigs) { continue }
+        visited.set(v % dbg.n_unitigs);
 
         oris[v % dbg.n_unitigs] = match (dir, v < dbg.n_unitigs) {
             (Direction::Forward, true) => Orientation::Forward,
@@ -108,7 +139,7 @@ fn bfs(mut root: usize, dir: Direction, oris: &mut [Orientation], visited: &mut
 }
 
 // BFS that can switch direction in the middle of the search.
-fn direction_switching_bfs(mut root: usize, root_dir: Direction, oris: &mut [Orientation], visited: &mut [bool], dbg: &DBG ) {
+fn direction_switching_bfs(mut root: usize, root_dir: Direction, oris: &mut [Orientation], visited: &mut BitSet, dbg: &DBG ) {
 
     if root_dir == Direction::Backward {
         // If we are going backward, it's the same as walking forward but
@@ -121,8 +152,8 @@ fn direction_switching_bfs(mut root: usize, root_dir: Direction, oris: &mut [Ori
 
     while let Some((v, dir)) = queue.pop_front() {
 
-        if visited[v % dbg.n_unitigs] { continue }
-        visited[v % dbg.n_unitigs] = true;
+        if visited.contains(v % dbg.n_unitigs) { continue }
+        visited.set(v % dbg.n_unitigs);
 
         oris[v % dbg.n_unitigs] = match (dir, v < dbg.n_unitigs) {
             (Direction::Forward, true) => Orientation::Forward,
@@ -148,12 +179,12 @@ pub fn pick_orientations_with_non_switching_bfs(dbg: &DBG) -> Vec<Orientation>{
     let mut orientations = Vec::<Orientation>::new();
     orientations.resize(n, Orientation::Forward);
 
-    let mut visited = vec![false; n];
+    let mut visited = BitSet::new(n);
 
     for v in 0..n {
-        if !visited[v] {
+        if !visited.contains(v) {
             bfs(v, Direction::Forward, &mut orientations, &mut visited, dbg); // Visits v
-            visited[v] = false;
+            visited.unset(v);
             bfs(v, Direction::Backward, &mut orientations, &mut visited, dbg); // Visits v again
         }
     };
@@ -167,7 +198,7 @@ pub fn pick_orientations_with_switching_bfs(dbg: &DBG) -> Vec<Orientation>{
     let mut orientations = Vec::<Orientation>::new();
     orientations.resize(n, Orientation::Forward);
 
-    let mut visited = vec![false; n];
+    let mut visited = BitSet::new(n);
 
     for v in 0..n {
         direction_switching_bfs(v, Direction::Forward, &mut orientations, &mut visited, dbg); // Visits v
@@ -176,10 +207,131 @@ pub fn pick_orientations_with_switching_bfs(dbg: &DBG) -> Vec<Orientation>{
     orientations
 }
 
+// An arc of the bipartite "gluing" graph used by `pick_orientations_bipartite_matching`:
+// matching left vertex `u` (the outgoing side of unitig u) to right vertex `v`
+// (the incoming side of unitig v) means "glue u immediately before v", and
+// `u_ori`/`v_ori` are the orientations that gluing implies for each of them.
+#[derive(Copy, Clone, Debug)]
+struct GlueArc {
+    v: usize,
+    u_ori: Orientation,
+    v_ori: Orientation,
+}
+
+/// Picks orientations via a minimum path cover: a maximum matching in the
+/// bipartite graph with a left vertex per unitig's outgoing side and a right
+/// vertex per unitig's incoming side, where a matched arc means "glue u
+/// immediately before v". The matched arcs partition the unitigs into
+/// vertex-disjoint chains (and possibly cycles), and n - |matching| is the
+/// minimum number of chains needed to cover the unitigs this way.
+///
+/// This is *not* a provably minimum source count against `evaluate`: the
+/// matching forces each unitig to be used as a predecessor for at most one
+/// successor, but `evaluate` only requires *some* predecessor, so a single
+/// unitig sitting at a branch point can cover several successors at once
+/// without being "used up". That gives `evaluate` more freedom than this
+/// reduction models, so it can do strictly better than the path cover this
+/// computes (see `pick_bipartite_matching_vs_brute_force` in the tests, which finds
+/// counterexamples by brute force). Treat this as a stronger heuristic than
+/// the BFS variants, not an exact algorithm -- hence the name no longer
+/// claims optimality.
+pub fn pick_orientations_bipartite_matching(dbg: &DBG) -> Vec<Orientation> {
+    let n = dbg.n_unitigs;
+
+    // adj[u] lists every v such that some orientation of u can be glued
+    // directly before some orientation of v, tagging the implied orientations.
+    let mut adj: Vec<Vec<GlueArc>> = vec![Vec::new(); n];
+    let mut g = BipartiteGraph::new(n, n);
+    for a in 0..(2 * n) {
+        let u = a % n;
+        let u_ori = if a < n { Orientation::Forward } else { Orientation::Reverse };
+        for &b in dbg.out_edges[a].iter() {
+            let v = b % n;
+            let v_ori = if b < n { Orientation::Forward } else { Orientation::Reverse };
+            adj[u].push(GlueArc { v, u_ori, v_ori });
+            g.add_arc(u, v);
+        }
+    }
+
+    let (match_left, _match_right) = matching::hopcroft_karp(&g);
+
+    // Look up the orientation tag of the arc actually used between u and its
+    // matched successor v. There may be several parallel arcs for the same
+    // (u, v) pair with different orientation tags (e.g. both a Forward/Forward
+    // and a Reverse/Reverse border match), so this must pick the one whose
+    // `u_ori` agrees with the orientation already fixed for u earlier in the
+    // chain, not just the first arc found; `None` means no arc realizes the
+    // glue under that orientation, so the chain has to stop here.
+    let arc_tag = |u: usize, u_ori: Orientation, v: usize| -> Option<GlueArc> {
+        adj[u].iter().find(|arc| arc.v == v && arc.u_ori == u_ori).copied()
+    };
+
+    let mut orientations = vec![Orientation::Forward; n];
+    let mut assigned = vec![false; n];
+
+    // Walk linear chains, starting from unitigs that are not the target of
+    // any matched arc (i.e. have no predecessor in the matching).
+    let has_predecessor: Vec<bool> = {
+        let mut v = vec![false; n];
+        for &m in match_left.iter().flatten() {
+            v[m] = true;
+        }
+        v
+    };
+
+    for start in 0..n {
+        if assigned[start] || has_predecessor[start] {
+            continue;
+        }
+        assigned[start] = true;
+        orientations[start] = Orientation::Forward; // Free choice: nothing constrains the chain's root.
+        let mut u = start;
+        while let Some(v) = match_left[u] {
+            if assigned[v] {
+                break; // Would re-enter an already assigned chain: stop here.
+            }
+            let Some(tag) = arc_tag(u, orientations[u], v) else {
+                // No parallel arc realizes the glue under u's fixed
+                // orientation; reject this arc and let v start its own chain
+                // (one extra source).
+                break;
+            };
+            orientations[v] = tag.v_ori;
+            assigned[v] = true;
+            u = v;
+        }
+    }
+
+    // Anything left unassigned at this point is part of a matched cycle
+    // (every vertex on it has a predecessor). Break each cycle at an
+    // arbitrary vertex, which is exactly the "drop one arc per cycle" rule.
+    for start in 0..n {
+        if assigned[start] {
+            continue;
+        }
+        assigned[start] = true;
+        orientations[start] = Orientation::Forward;
+        let mut u = start;
+        while let Some(v) = match_left[u] {
+            if assigned[v] {
+                break;
+            }
+            let Some(tag) = arc_tag(u, orientations[u], v) else {
+                break;
+            };
+            orientations[v] = tag.v_ori;
+            assigned[v] = true;
+            u = v;
+        }
+    }
+
+    orientations
+}
+
 // Returns the number of unitigs that do not have a predecessor
 pub fn evaluate(choices: &[Orientation], dbg: &DBG) -> usize{
     let n = dbg.n_unitigs;
-    let mut has_pred = vec![false; n];
+    let mut has_pred = BitSet::new(n);
 
     #[allow(unused_parens)]
     for v in 0..(n*2){
@@ -187,13 +339,12 @@ pub fn evaluate(choices: &[Orientation], dbg: &DBG) -> usize{
             let v_flipped = (choices[v%n] == Orientation::Reverse);
             let u_flipped = (choices[u%n] == Orientation::Reverse);
             if (v_flipped == (v >= n)) && (u_flipped == (u >= n)){
-                has_pred[u % n] = true;
+                has_pred.set(u % n);
             }
         }
     }
-    
-    // Return the number of 1-bits in has_pred
-    has_pred.iter().fold(0_usize, |sum, &x| sum + x as usize)
+
+    has_pred.iter_set_bits().count()
 }
 
 #[cfg(test)]
@@ -322,5 +473,73 @@ mod tests {
         assert!(orientations == ans1 || orientations == ans2);
 
     }
+
+    #[test]
+    fn test_bipartite_matching_picks_orientation_consistent_arc(){
+        // Unitigs 1 and 2 (GTCA, CATGT) glue together via two parallel border
+        // overlaps -- a Forward/Forward one and a Reverse/Reverse one -- so
+        // `arc_tag` has to pick the one consistent with unitig 1's
+        // orientation as already fixed by its arc from unitig 0, not
+        // whichever parallel arc happens to come first. Picking the wrong one
+        // breaks the chain in two instead of leaving the true minimum of one
+        // source.
+        let k = 3;
+        let data: Vec<&[u8]> = vec![b"AATG", b"GTCA", b"CATGT"];
+
+        let dbg = get_dbg(data, k);
+        let orientations = pick_orientations_bipartite_matching(&dbg);
+
+        let n_has_pred = evaluate(&orientations, &dbg);
+        let n_sources = dbg.n_unitigs - n_has_pred;
+        assert_eq!(n_sources, 1);
+    }
+
+    // Brute-forces every 2^n orientation assignment against `evaluate` and
+    // returns the true minimum source count, for cross-checking the
+    // heuristics on inputs small enough to enumerate exhaustively.
+    fn brute_force_min_sources(dbg: &DBG) -> usize {
+        let n = dbg.n_unitigs;
+        (0..(1u32 << n))
+            .map(|mask| {
+                let choices: Vec<Orientation> = (0..n)
+                    .map(|i| if (mask >> i) & 1 == 1 { Reverse } else { Forward })
+                    .collect();
+                dbg.n_unitigs - evaluate(&choices, dbg)
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn pick_bipartite_matching_vs_brute_force(){
+        // Counterexample found by brute-forcing all 2^n assignments: the
+        // matching-based reduction in `pick_orientations_bipartite_matching` forces
+        // each unitig to be used as a predecessor for at most one successor,
+        // but `evaluate` lets a unitig at a branch point satisfy several
+        // successors at once, so the matching's path cover can be strictly
+        // worse than the true minimum (and even worse than the plain BFS
+        // heuristic, which happens to stumble onto a better choice here).
+        let k = 4;
+        let data: Vec<&[u8]> = vec![b"TCATTC", b"CATCCA", b"CCAAAGAA", b"ATGCTAT", b"GTTCTTTC"];
+
+        let dbg = get_dbg(data, k);
+
+        let true_min = brute_force_min_sources(&dbg);
+        assert_eq!(true_min, 1);
+
+        let matching_orientations = pick_orientations_bipartite_matching(&dbg);
+        let matching_sources = dbg.n_unitigs - evaluate(&matching_orientations, &dbg);
+        assert_eq!(matching_sources, 4);
+
+        let bfs_orientations = pick_orientations_with_non_switching_bfs(&dbg);
+        let bfs_sources = dbg.n_unitigs - evaluate(&bfs_orientations, &dbg);
+        assert_eq!(bfs_sources, 2);
+
+        // Document the gap rather than hide it: `pick_orientations_bipartite_matching`
+        // is not actually optimal against `evaluate`, and can be beaten by
+        // the simpler BFS heuristic.
+        assert!(matching_sources > true_min);
+        assert!(matching_sources > bfs_sources);
+    }
 }
 
@@ -7,8 +7,11 @@ use clap::{Command, Arg};
 
 use log::info;
 
-use unitig_flipper::dbg::Orientation;
-use unitig_flipper::optimize_unitig_orientation;
+use unitig_flipper::dbg::{Orientation, DBG};
+use unitig_flipper::dbg_rethink::{pick_orientations_components, pick_orientations_greedy_longest, pick_orientations_matching};
+use unitig_flipper::gfa::write_gfa;
+use unitig_flipper::simplitigs::compute_simplitigs;
+use unitig_flipper::{build_dbg, pick_orientations_bipartite_matching, pick_orientations_with_non_switching_bfs};
 use unitig_flipper::SeqStream;
 
 struct MyReader {
@@ -52,25 +55,94 @@ fn main() {
             .short('k')
             .required(true)
             .value_parser(clap::value_parser!(usize))
+        )
+        .arg(Arg::new("output-format")
+            .help("Output format: fasta/fastq records in their chosen orientation, or the oriented graph as GFA1")
+            .long("output-format")
+            .value_parser(["seq", "gfa"])
+            .default_value("seq")
+        )
+        .arg(Arg::new("method")
+            .help("Orientation heuristic to use")
+            .long("method")
+            .value_parser(["bfs", "bipartite-matching", "matching", "components", "greedy-longest"])
+            .default_value("bfs")
+        )
+        .arg(Arg::new("merge")
+            .help("Instead of reorienting records one by one, stitch oriented unitigs along their chains into merged simplitigs")
+            .long("merge")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(Arg::new("parallel")
+            .help("Build the de Bruijn graph with the rayon-parallel construction instead of the default single-threaded one")
+            .long("parallel")
+            .action(clap::ArgAction::SetTrue)
+        )
+        .arg(Arg::new("threads")
+            .help("Number of threads to use with --parallel (default: rayon's global pool size)")
+            .long("threads")
+            .value_parser(clap::value_parser!(usize))
+            .requires("parallel")
         );
 
     let cli_matches = cli.get_matches();
     let infile: &PathBuf = cli_matches.get_one("input").unwrap();
     let outfile: &PathBuf = cli_matches.get_one("output").unwrap();
     let k: usize = *cli_matches.get_one("k").unwrap();
+    let output_format: &String = cli_matches.get_one("output-format").unwrap();
+    let method: &String = cli_matches.get_one("method").unwrap();
+    let merge: bool = cli_matches.get_flag("merge");
+    let parallel: bool = cli_matches.get_flag("parallel");
+    let threads: Option<usize> = cli_matches.get_one("threads").copied();
 
     let reader = DynamicFastXReader::from_file(infile).unwrap();
-    let mut writer = DynamicFastXWriter::new_to_file(outfile).unwrap();
-
     let reader = MyReader{inner: reader};
 
-    let orientations = optimize_unitig_orientation(reader, k);
+    let dbg = if parallel {
+        let (unitigs, unitigs_rc) = unitig_flipper::read_unitigs(reader);
+        DBG::build_parallel(unitigs, unitigs_rc, k, threads)
+    } else {
+        build_dbg(reader, k)
+    };
+
+    let orientations = match method.as_str() {
+        "bipartite-matching" => pick_orientations_bipartite_matching(&dbg),
+        "matching" => pick_orientations_matching(&dbg),
+        "components" => {
+            let (orientations, is_circular) = pick_orientations_components(&dbg);
+            let n_circular = is_circular.iter().filter(|&&c| c).count();
+            info!("{} unitigs belong to circular components with no canonical start", n_circular);
+            orientations
+        },
+        "greedy-longest" => pick_orientations_greedy_longest(&dbg),
+        _ => pick_orientations_with_non_switching_bfs(&dbg),
+    };
 
     let n_forward = orientations.iter().fold(0_usize, |acc, &x| (acc + (x == Orientation::Forward) as usize));
     info!("{}% Forward", 100.0 * n_forward as f64 / orientations.len() as f64);
 
     info!("Writing output");
 
+    if output_format == "gfa" {
+        let mut out = std::fs::File::create(outfile).unwrap();
+        write_gfa(&mut out, &dbg, &orientations, k).unwrap();
+        return;
+    }
+
+    if merge {
+        let simplitigs = compute_simplitigs(&dbg, &orientations, k);
+        let total_len: usize = simplitigs.iter().map(|s| s.len()).sum();
+        info!("Merged {} unitigs into {} simplitigs ({} total bases)", dbg.n_unitigs, simplitigs.len(), total_len);
+
+        let mut writer = DynamicFastXWriter::new_to_file(outfile).unwrap();
+        for (i, seq) in simplitigs.iter().enumerate() {
+            let rec = OwnedRecord { head: format!("simplitig_{}", i).into_bytes(), seq: seq.clone(), qual: None };
+            writer.write_owned_record(&rec).unwrap();
+        }
+        return;
+    }
+
+    let mut writer = DynamicFastXWriter::new_to_file(outfile).unwrap();
     let mut reader = DynamicFastXReader::from_file(infile).unwrap();
     let mut seq_idx = 0_usize;
     while let Some(rec) = reader.read_next().unwrap(){
@@ -86,6 +158,6 @@ fn main() {
 
         writer.write_owned_record(&new_rec).unwrap();
         seq_idx += 1;
-    }    
+    }
 
 }
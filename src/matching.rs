@@ -0,0 +1,118 @@
+// Generic Hopcroft-Karp maximum bipartite matching, used by
+// `pick_orientations_bipartite_matching` to find a minimum path cover of the unitig
+// graph. Kept independent of `DBG` so it can be unit tested on its own.
+
+const NIL: usize = usize::MAX;
+
+/// A bipartite graph with `n_left` left vertices and `n_right` right vertices,
+/// given as an adjacency list from each left vertex to the right vertices it
+/// connects to.
+pub struct BipartiteGraph {
+    pub n_left: usize,
+    pub n_right: usize,
+    pub adj: Vec<Vec<usize>>,
+}
+
+impl BipartiteGraph {
+    pub fn new(n_left: usize, n_right: usize) -> Self {
+        BipartiteGraph { n_left, n_right, adj: vec![Vec::new(); n_left] }
+    }
+
+    pub fn add_arc(&mut self, left: usize, right: usize) {
+        self.adj[left].push(right);
+    }
+}
+
+/// Runs Hopcroft-Karp on `g` and returns `(match_left, match_right)`, where
+/// `match_left[u] == Some(v)` iff left vertex `u` is matched to right vertex
+/// `v` (and symmetrically for `match_right`). Unmatched vertices map to `None`.
+pub fn hopcroft_karp(g: &BipartiteGraph) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let mut match_left = vec![NIL; g.n_left];
+    let mut match_right = vec![NIL; g.n_right];
+    let mut dist = vec![0_usize; g.n_left];
+
+    loop {
+        if !bfs(g, &match_left, &match_right, &mut dist) {
+            break;
+        }
+        for u in 0..g.n_left {
+            if match_left[u] == NIL && dfs(g, u, &mut match_left, &mut match_right, &mut dist) {
+                // Augmenting path found and applied in `dfs`.
+            }
+        }
+    }
+
+    let to_option = |v: usize| if v == NIL { None } else { Some(v) };
+    (
+        match_left.into_iter().map(to_option).collect(),
+        match_right.into_iter().map(to_option).collect(),
+    )
+}
+
+// Builds layers of free left vertices by alternating BFS; returns whether
+// any augmenting path exists this round.
+fn bfs(g: &BipartiteGraph, match_left: &[usize], match_right: &[usize], dist: &mut [usize]) -> bool {
+    let mut queue = std::collections::VecDeque::new();
+
+    for u in 0..g.n_left {
+        if match_left[u] == NIL {
+            dist[u] = 0;
+            queue.push_back(u);
+        } else {
+            dist[u] = usize::MAX;
+        }
+    }
+
+    let mut found_free_right = false;
+    while let Some(u) = queue.pop_front() {
+        for &v in g.adj[u].iter() {
+            let w = match_right[v];
+            if w == NIL {
+                found_free_right = true;
+            } else if dist[w] == usize::MAX {
+                dist[w] = dist[u] + 1;
+                queue.push_back(w);
+            }
+        }
+    }
+
+    found_free_right
+}
+
+// Tries to extend an alternating path from free left vertex `u`, flipping
+// matched/unmatched arcs along the way if it reaches a free right vertex.
+fn dfs(g: &BipartiteGraph, u: usize, match_left: &mut [usize], match_right: &mut [usize], dist: &mut [usize]) -> bool {
+    for i in 0..g.adj[u].len() {
+        let v = g.adj[u][i];
+        let w = match_right[v];
+        if w == NIL || (dist[w] == dist[u] + 1 && dfs(g, w, match_left, match_right, dist)) {
+            match_left[u] = v;
+            match_right[v] = u;
+            return true;
+        }
+    }
+    dist[u] = usize::MAX;
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_matching() {
+        // Left 0 -> Right {0, 1}
+        // Left 1 -> Right {0}
+        // Left 2 -> Right {1}
+        // Maximum matching has size 2.
+        let mut g = BipartiteGraph::new(3, 2);
+        g.add_arc(0, 0);
+        g.add_arc(0, 1);
+        g.add_arc(1, 0);
+        g.add_arc(2, 1);
+
+        let (match_left, _match_right) = hopcroft_karp(&g);
+        let matched = match_left.iter().filter(|m| m.is_some()).count();
+        assert_eq!(matched, 2);
+    }
+}
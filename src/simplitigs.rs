@@ -1,80 +1,145 @@
-use std::collections::{HashSet};
-use rand::seq::SliceRandom;
-
-fn reverse_complement(s: &str) -> String {
-    s.chars().map(|c| match c {
-        'A' => 'T',
-        'C' => 'G',
-        'G' => 'C',
-        'T' => 'A',
-        _ => unreachable!(),
-    }).collect::<String>().chars().rev().collect()
-}
+use crate::dbg::{Orientation, DBG};
 
-fn extend_simplitig_forward(k: usize, mut simplitig: String, k_set: &mut HashSet<String>) -> String {
-    let mut extending = true;
-    while extending {
-        extending = false;
-        let q = &simplitig[simplitig.len() - k + 1..];
-        for x in &['A', 'C', 'G', 'T'] {
-            let kmer = format!("{}{}", q, x);
-            if k_set.contains(&kmer) {
-                extending = true;
-                simplitig.push(*x);
-                k_set.remove(&kmer);
-                k_set.remove(&reverse_complement(&kmer));
-                break;
-            }
+/// Greedily concatenates unitigs along the chains implied by a chosen
+/// orientation vector to produce a spectrum-preserving string set (SPSS):
+/// every k-mer of the input unitigs occurs in exactly one of the returned
+/// sequences, but consecutive unitigs that are glued together by a DBG edge
+/// have their shared `(k-1)`-mer overlap collapsed, so the total output is
+/// shorter than simply concatenating all the oriented unitigs end to end.
+///
+/// This walks the same edges `evaluate` counts as giving a unitig a
+/// predecessor: a unitig starts a new simplitig when it has no such
+/// predecessor, and each simplitig is extended along the first unvisited
+/// such successor only. Any unitigs left over after that (those on cycles
+/// where every unitig has a predecessor, and those whose predecessors' chains
+/// continued into a different successor at a branch point) each start a
+/// further simplitig, taken in index order.
+pub fn compute_simplitigs(dbg: &DBG, orientations: &[Orientation], k: usize) -> Vec<Vec<u8>> {
+    let n = dbg.n_unitigs;
+    let mut visited = vec![false; n];
+    let mut simplitigs = Vec::new();
+
+    // Linear chains: start only from unitigs with no chosen-orientation predecessor.
+    for start in 0..n {
+        if !visited[start] && !has_chosen_predecessor(dbg, orientations, start) {
+            simplitigs.push(extend_chain(dbg, orientations, k, start, &mut visited));
         }
     }
-    simplitig
+
+    // Whatever is left is either on a cycle or was skipped at a branch point
+    // by the chains above; start a new chain from each such unitig.
+    for start in 0..n {
+        if !visited[start] {
+            simplitigs.push(extend_chain(dbg, orientations, k, start, &mut visited));
+        }
+    }
+
+    simplitigs
 }
 
-fn get_maximal_simplitig(k_set: &mut HashSet<String>, initial_kmer: String) -> String {
-    let mut simplitig = initial_kmer.clone();
-    k_set.remove(&initial_kmer);
-    k_set.remove(&reverse_complement(&initial_kmer));
-    simplitig = extend_simplitig_forward(initial_kmer.len(), simplitig, k_set);
-    let mut simplitig_rc = reverse_complement(&simplitig);
-    simplitig_rc = extend_simplitig_forward(initial_kmer.len(), simplitig_rc, k_set);
-    simplitig_rc
+// Only edges that agree with the chosen orientation on both ends survive in
+// the final oriented graph -- the same test `evaluate` uses.
+fn is_chosen_node(orientations: &[Orientation], n: usize, node: usize) -> bool {
+    (orientations[node % n] == Orientation::Reverse) == (node >= n)
 }
 
-fn compute_simplitigs(kmers: Vec<String>) -> HashSet<String> {
-    let mut k_set: HashSet<String> = HashSet::new();
-    for kmer in &kmers {
-        k_set.insert(kmer.clone());
-        k_set.insert(reverse_complement(kmer));
+fn chosen_node_of(orientations: &[Orientation], n: usize, unitig_id: usize) -> usize {
+    if orientations[unitig_id] == Orientation::Reverse { unitig_id + n } else { unitig_id }
+}
+
+fn has_chosen_predecessor(dbg: &DBG, orientations: &[Orientation], unitig_id: usize) -> bool {
+    let n = dbg.n_unitigs;
+    let node = chosen_node_of(orientations, n, unitig_id);
+    dbg.in_edges[node].iter().any(|&p| is_chosen_node(orientations, n, p))
+}
+
+fn extend_chain(dbg: &DBG, orientations: &[Orientation], k: usize, start: usize, visited: &mut [bool]) -> Vec<u8> {
+    let n = dbg.n_unitigs;
+    let mut seq = oriented_seq(dbg, start, orientations[start]);
+    visited[start] = true;
+    let mut node = chosen_node_of(orientations, n, start);
+
+    while let Some(next) = dbg.out_edges[node]
+        .iter()
+        .copied()
+        .find(|&u| is_chosen_node(orientations, n, u) && !visited[u % n])
+    {
+        let next_unitig = next % n;
+        let next_seq = oriented_seq(dbg, next_unitig, orientations[next_unitig]);
+        seq.extend_from_slice(&next_seq[k - 1..]);
+        visited[next_unitig] = true;
+        node = next;
     }
-    let mut simplitigs: HashSet<String> = HashSet::new();
-    while !k_set.is_empty() {
-        let initial_kmer = k_set.iter().next().unwrap().clone(); // "Random choice"
-        let simplitig = get_maximal_simplitig(&mut k_set, initial_kmer);
-        simplitigs.insert(simplitig);
+
+    seq
+}
+
+fn oriented_seq(dbg: &DBG, unitig_id: usize, orientation: Orientation) -> Vec<u8> {
+    let rec = dbg.unitig_db.get(unitig_id);
+    let mut seq = rec.seq.to_vec();
+    if orientation == Orientation::Reverse {
+        jseqio::reverse_complement_in_place(&mut seq);
     }
-    simplitigs
+    seq
 }
 
 #[cfg(test)]
 mod tests {
-    use super::compute_simplitigs;
+    use super::*;
+    use std::collections::HashMap;
+    use crate::pick_orientations_bipartite_matching;
 
+    fn get_dbg(seqs: Vec<&[u8]>, k: usize) -> DBG {
+        let rc_seqs = seqs.iter().map(|s| jseqio::reverse_complement(s));
 
-    #[test]
-    fn small_example(){
-        let k = 6;
-        let raw_input: Vec<&[u8]> = vec![b"AAACCC", b"CCCGGG", b"GGGTTT"];
-        let kmers: Vec<String> = raw_input.iter().map(|&s| s.windows(k)).fold(Vec::<String>::new(), |mut acc, it| {
-            let s_kmers: Vec<String> = it.into_iter().map(|s| String::from_utf8_lossy(s).into_owned()).collect();
-            acc.extend(s_kmers);
-            acc
-        });
-        let result = compute_simplitigs(kmers);
-        dbg!(result);
+        let mut db = jseqio::seq_db::SeqDB::new();
+        for seq in seqs.iter() {
+            db.push_seq(seq);
+        }
+
+        let mut rc_db = jseqio::seq_db::SeqDB::new();
+        for rc_seq in rc_seqs {
+            rc_db.push_seq(&rc_seq);
+        }
+
+        DBG::build(db, rc_db, k)
     }
 
-}
+    // A k-mer and its reverse complement represent the same underlying DNA
+    // fragment, so the spectrum is compared canonically (lexicographically
+    // smaller of the two) -- otherwise flipping a unitig's orientation would
+    // look like it changed the k-mer set, when it hasn't.
+    fn canonical_kmer(kmer: &[u8]) -> Vec<u8> {
+        let rc = jseqio::reverse_complement(kmer);
+        if rc < kmer.to_vec() { rc } else { kmer.to_vec() }
+    }
 
-// Todo:
-// String -> Vec[u8]
-// Use unitigs 
\ No newline at end of file
+    fn kmer_multiset(seqs: &[Vec<u8>], k: usize) -> HashMap<Vec<u8>, usize> {
+        let mut counts = HashMap::new();
+        for seq in seqs {
+            for w in seq.windows(k) {
+                *counts.entry(canonical_kmer(w)).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn compute_simplitigs_preserves_the_kmer_spectrum(){
+        let k = 3;
+        let data: Vec<&[u8]> = vec![b"AATG", b"GTCA", b"CATGT"];
+        let input: Vec<Vec<u8>> = data.iter().map(|s| s.to_vec()).collect();
+
+        let dbg = get_dbg(data, k);
+        let orientations = pick_orientations_bipartite_matching(&dbg);
+
+        let simplitigs = compute_simplitigs(&dbg, &orientations, k);
+
+        // Merging collapses overlaps, so there must be strictly fewer (or
+        // equal, if nothing could be glued) output sequences than input
+        // unitigs, but the k-mer spectrum underneath has to come out exactly
+        // the same.
+        assert!(simplitigs.len() <= dbg.n_unitigs);
+        assert_eq!(kmer_multiset(&input, k), kmer_multiset(&simplitigs, k));
+    }
+}